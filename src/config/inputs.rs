@@ -1,11 +1,14 @@
 use crate::config::fragments;
 use crate::update_agent::DEFAULT_STEADY_INTERVAL_SECS;
+use crate::weekly::utils as weekly;
 use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Utc};
 use fn_error_context::context;
 use log::debug;
 use ordered_float::NotNan;
 use serde::Serialize;
 use std::num::NonZeroU64;
+use std::time::Duration;
 
 /// Runtime configuration holding environmental inputs.
 #[derive(Debug, Serialize)]
@@ -40,12 +43,11 @@ impl ConfigInput {
             fragments.push(frag);
         }
 
-        let cfg = Self::merge_fragments(fragments);
-        Ok(cfg)
+        Self::merge_fragments(fragments)
     }
 
     /// Merge multiple fragments into a single configuration.
-    pub(crate) fn merge_fragments(fragments: Vec<fragments::ConfigFragment>) -> Self {
+    pub(crate) fn merge_fragments(fragments: Vec<fragments::ConfigFragment>) -> Result<Self> {
         let mut agents = vec![];
         let mut cincinnatis = vec![];
         let mut updates = vec![];
@@ -72,14 +74,15 @@ impl ConfigInput {
             }
         }
 
-        Self {
+        Ok(Self {
             agent: AgentInput::from_fragments(agents),
             cincinnati: CincinnatiInput::from_fragments(cincinnatis),
-            updates: UpdateInput::from_fragments(updates),
+            updates: UpdateInput::from_fragments(updates)
+                .context("failed to resolve update strategy config")?,
             identity: IdentityInput::from_fragments(identities),
             #[cfg(feature = "drogue")]
             drogue: DrogueInput::from_fragments(drogues),
-        }
+        })
     }
 }
 
@@ -174,6 +177,64 @@ pub(crate) struct UpdateInput {
     pub(crate) fleet_lock: FleetLockInput,
     /// `periodic` strategy config.
     pub(crate) periodic: PeriodicInput,
+    /// Blackout windows that veto updates regardless of strategy.
+    pub(crate) exclusions: Vec<ExclusionWindowInput>,
+}
+
+/// A single exclusion/blackout window.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) enum ExclusionWindowInput {
+    Weekly(PeriodicIntervalInput),
+    Recurring(RecurringIntervalInput),
+}
+
+impl ExclusionWindowInput {
+    /// Whether `now` falls inside this exclusion window.
+    fn is_active(&self, now: &DateTime<Utc>, tz: &chrono_tz::Tz) -> Result<bool> {
+        match self {
+            Self::Weekly(interval) => is_in_weekly_window(now, tz, interval),
+            Self::Recurring(recurring) => {
+                let rule = weekly::RecurrenceRule::parse(&recurring.rrule)
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
+                let length = Duration::from_secs(u64::from(recurring.length_minutes) * 60);
+                rule.is_active(now, &length, tz).map_err(|e| anyhow::anyhow!("{}", e))
+            }
+        }
+    }
+}
+
+/// Whether `now` (evaluated in `tz`) falls inside the weekly window
+/// described by `interval`, wrapping around the end of the week.
+fn is_in_weekly_window(
+    now: &DateTime<Utc>,
+    tz: &chrono_tz::Tz,
+    interval: &PeriodicIntervalInput,
+) -> Result<bool> {
+    let day = weekly::weekday_from_string(&interval.start_day)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let (hour, minute) = weekly::time_from_string(&interval.start_time)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let length = Duration::from_secs(u64::from(interval.length_minutes) * 60);
+    weekly::check_duration(&length).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let length = ChronoDuration::from_std(length)?;
+
+    // Check this week's and last week's occurrence, since a still-open
+    // window from last week can stretch into this one.
+    let today = now.with_timezone(tz).date_naive();
+    let days_since_day = (i64::from(today.weekday().num_days_from_monday())
+        - i64::from(day.num_days_from_monday()))
+    .rem_euclid(7);
+    let this_week_date = today - ChronoDuration::days(days_since_day);
+
+    for start_date in [this_week_date, this_week_date - ChronoDuration::weeks(1)] {
+        let start = weekly::resolve_local_instant(tz, start_date, hour, minute)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        if start <= *now && *now < start + length {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
 }
 
 /// Config for "fleet_lock" strategy.
@@ -188,9 +249,16 @@ pub(crate) struct FleetLockInput {
 pub(crate) struct PeriodicInput {
     /// Set of updates windows.
     pub(crate) intervals: Vec<PeriodicIntervalInput>,
+    /// Set of recurrence-rule update windows (monthly/yearly cadences that
+    /// don't fit a weekly grid, e.g. "second Tuesday of each month").
+    pub(crate) recurring: Vec<RecurringIntervalInput>,
     /// A time zone in the IANA Time Zone Database or "localtime".
     /// Defaults to "UTC".
     pub(crate) time_zone: String,
+    /// `time_zone` resolved to a concrete IANA zone, used to evaluate
+    /// windows in local wall-clock time (DST-correct).
+    #[serde(skip)]
+    pub(crate) tz: chrono_tz::Tz,
 }
 
 /// Update window for a "periodic" interval.
@@ -201,8 +269,16 @@ pub(crate) struct PeriodicIntervalInput {
     pub(crate) length_minutes: u32,
 }
 
+/// Update window expressed as an RFC 5545 recurrence rule (`DTSTART` plus
+/// an `RRULE` line) and a length, for cadences a weekly grid can't express.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct RecurringIntervalInput {
+    pub(crate) rrule: String,
+    pub(crate) length_minutes: u32,
+}
+
 impl UpdateInput {
-    fn from_fragments(fragments: Vec<fragments::UpdateFragment>) -> Self {
+    fn from_fragments(fragments: Vec<fragments::UpdateFragment>) -> Result<Self> {
         let mut allow_downgrade = false;
         let mut enabled = true;
         let mut strategy = String::new();
@@ -211,8 +287,11 @@ impl UpdateInput {
         };
         let mut periodic = PeriodicInput {
             intervals: vec![],
+            recurring: vec![],
             time_zone: "UTC".to_string(),
+            tz: chrono_tz::UTC,
         };
+        let mut exclusions = vec![];
 
         for snip in fragments {
             if let Some(a) = snip.allow_downgrade {
@@ -235,9 +314,11 @@ impl UpdateInput {
                 }
                 if let Some(win) = w.window {
                     for entry in win {
-                        for day in entry.days {
+                        for day in weekly::expand_day_keywords(&entry.days)
+                            .map_err(|e| anyhow::anyhow!("{}", e))?
+                        {
                             let interval = PeriodicIntervalInput {
-                                start_day: day,
+                                start_day: day.to_string(),
                                 start_time: entry.start_time.clone(),
                                 length_minutes: entry.length_minutes,
                             };
@@ -245,17 +326,106 @@ impl UpdateInput {
                         }
                     }
                 }
+                if let Some(recurring) = w.recurring {
+                    for entry in recurring {
+                        periodic.recurring.push(RecurringIntervalInput {
+                            rrule: entry.rrule,
+                            length_minutes: entry.length_minutes,
+                        });
+                    }
+                }
+            }
+            // Exclusions merge additively, unlike the last-wins settings above.
+            if let Some(excl) = snip.exclude {
+                if let Some(win) = excl.window {
+                    for entry in win {
+                        for day in weekly::expand_day_keywords(&entry.days)
+                            .map_err(|e| anyhow::anyhow!("{}", e))?
+                        {
+                            exclusions.push(ExclusionWindowInput::Weekly(PeriodicIntervalInput {
+                                start_day: day.to_string(),
+                                start_time: entry.start_time.clone(),
+                                length_minutes: entry.length_minutes,
+                            }));
+                        }
+                    }
+                }
+                if let Some(recurring) = excl.recurring {
+                    for entry in recurring {
+                        exclusions.push(ExclusionWindowInput::Recurring(RecurringIntervalInput {
+                            rrule: entry.rrule,
+                            length_minutes: entry.length_minutes,
+                        }));
+                    }
+                }
             }
         }
 
-        Self {
+        periodic.tz = resolve_time_zone(&periodic.time_zone)?;
+
+        Ok(Self {
             allow_downgrade,
             enabled,
             strategy,
             fleet_lock,
             periodic,
+            exclusions,
+        })
+    }
+
+    /// Whether `now` falls inside any configured exclusion/blackout window.
+    /// An active exclusion strictly overrides any periodic or fleet-lock
+    /// allowance: callers must treat `true` as "not permitted" regardless
+    /// of the configured strategy.
+    pub(crate) fn is_excluded(&self, now: &DateTime<Utc>) -> Result<bool> {
+        for exclusion in &self.exclusions {
+            if exclusion.is_active(now, &self.periodic.tz)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Resolve a configured `time_zone` string to a concrete IANA zone.
+/// The sentinel value `"localtime"` detects the host's configured zone.
+#[context("failed to resolve configured time zone '{}'", time_zone)]
+fn resolve_time_zone(time_zone: &str) -> Result<chrono_tz::Tz> {
+    if time_zone != "localtime" {
+        return time_zone
+            .parse()
+            .map_err(|_| anyhow::anyhow!("unknown time zone '{}'", time_zone));
+    }
+
+    let name = host_time_zone_name().context("failed to detect host time zone")?;
+    name.parse()
+        .map_err(|_| anyhow::anyhow!("host time zone '{}' is not a known IANA zone", name))
+}
+
+/// Detect the host's configured IANA time zone name from `/etc/localtime`
+/// (preferred) or `/etc/timezone` (fallback).
+fn host_time_zone_name() -> Result<String> {
+    if let Ok(target) = std::fs::read_link("/etc/localtime") {
+        if let Some(name) = zone_name_from_zoneinfo_path(&target) {
+            return Ok(name);
+        }
+    }
+
+    if let Ok(content) = std::fs::read_to_string("/etc/timezone") {
+        let name = content.trim();
+        if !name.is_empty() {
+            return Ok(name.to_string());
         }
     }
+
+    anyhow::bail!("could not determine host time zone from /etc/localtime or /etc/timezone")
+}
+
+/// Extract the `<Region>/<City>` zone name from a `/etc/localtime` symlink
+/// target such as `/usr/share/zoneinfo/Europe/Berlin`.
+fn zone_name_from_zoneinfo_path(path: &std::path::Path) -> Option<String> {
+    let (_, name) = path.to_str()?.split_once("zoneinfo/")?;
+    Some(name.to_string())
 }
 
 /// Config for the Drogue IoT agent.
@@ -339,3 +509,153 @@ impl DrogueInput {
         cfg
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_exclusion_weekly_window_is_active() {
+        let exclusion = ExclusionWindowInput::Weekly(PeriodicIntervalInput {
+            start_day: "Mon".to_string(),
+            start_time: "00:00".to_string(),
+            length_minutes: 24 * 60,
+        });
+        let tz = chrono_tz::UTC;
+
+        // 2023-08-07 is a Monday.
+        let inside = Utc.with_ymd_and_hms(2023, 8, 7, 12, 0, 0).unwrap();
+        assert!(exclusion.is_active(&inside, &tz).unwrap());
+
+        let outside = Utc.with_ymd_and_hms(2023, 8, 8, 12, 0, 0).unwrap();
+        assert!(!exclusion.is_active(&outside, &tz).unwrap());
+    }
+
+    #[test]
+    fn test_exclusion_recurring_window_is_active() {
+        let exclusion = ExclusionWindowInput::Recurring(RecurringIntervalInput {
+            rrule: "DTSTART:20230101T000000Z\nRRULE:FREQ=YEARLY;BYMONTHDAY=1".to_string(),
+            length_minutes: 24 * 60,
+        });
+        let tz = chrono_tz::UTC;
+
+        let inside = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert!(exclusion.is_active(&inside, &tz).unwrap());
+
+        let outside = Utc.with_ymd_and_hms(2024, 1, 2, 12, 0, 0).unwrap();
+        assert!(!exclusion.is_active(&outside, &tz).unwrap());
+    }
+
+    #[test]
+    fn test_exclusion_recurring_window_is_active_non_utc_tz() {
+        let exclusion = ExclusionWindowInput::Recurring(RecurringIntervalInput {
+            rrule: "DTSTART:20230103T020000Z\nRRULE:FREQ=MONTHLY;BYDAY=2TU;BYHOUR=2;BYMINUTE=0"
+                .to_string(),
+            length_minutes: 60,
+        });
+        let tz = chrono_tz::America::New_York;
+
+        // Second Tuesday of February 2024 is the 13th; 2am EST is 07:00 UTC.
+        let inside = Utc.with_ymd_and_hms(2024, 2, 13, 7, 30, 0).unwrap();
+        assert!(exclusion.is_active(&inside, &tz).unwrap());
+
+        // 2am UTC on the same day is 9pm EST the day before, outside the window.
+        let outside = Utc.with_ymd_and_hms(2024, 2, 13, 2, 30, 0).unwrap();
+        assert!(!exclusion.is_active(&outside, &tz).unwrap());
+    }
+
+    #[test]
+    fn test_is_excluded() {
+        let updates = UpdateInput {
+            allow_downgrade: false,
+            enabled: true,
+            strategy: "periodic".to_string(),
+            fleet_lock: FleetLockInput {
+                base_url: String::new(),
+            },
+            periodic: PeriodicInput {
+                intervals: vec![],
+                recurring: vec![],
+                time_zone: "UTC".to_string(),
+                tz: chrono_tz::UTC,
+            },
+            exclusions: vec![ExclusionWindowInput::Weekly(PeriodicIntervalInput {
+                start_day: "Mon".to_string(),
+                start_time: "00:00".to_string(),
+                length_minutes: 24 * 60,
+            })],
+        };
+
+        // An active exclusion is reported regardless of the configured
+        // strategy; it's up to the caller to treat it as an override.
+        let inside = Utc.with_ymd_and_hms(2023, 8, 7, 12, 0, 0).unwrap();
+        assert!(updates.is_excluded(&inside).unwrap());
+
+        let outside = Utc.with_ymd_and_hms(2023, 8, 8, 12, 0, 0).unwrap();
+        assert!(!updates.is_excluded(&outside).unwrap());
+    }
+
+    #[test]
+    fn test_exclusions_merge_additively_across_fragments() {
+        let frag_a = fragments::UpdateFragment {
+            allow_downgrade: None,
+            enabled: None,
+            strategy: None,
+            fleet_lock: None,
+            periodic: None,
+            exclude: Some(fragments::ExcludeFragment {
+                window: Some(vec![fragments::WindowFragment {
+                    days: vec!["monday".to_string()],
+                    start_time: "00:00".to_string(),
+                    length_minutes: 60,
+                }]),
+                recurring: None,
+            }),
+        };
+        let frag_b = fragments::UpdateFragment {
+            allow_downgrade: None,
+            enabled: None,
+            strategy: None,
+            fleet_lock: None,
+            periodic: None,
+            exclude: Some(fragments::ExcludeFragment {
+                window: None,
+                recurring: Some(vec![fragments::RecurringFragment {
+                    rrule: "DTSTART:20230101T000000Z\nRRULE:FREQ=YEARLY;BYMONTHDAY=1"
+                        .to_string(),
+                    length_minutes: 1440,
+                }]),
+            }),
+        };
+
+        // Exclusions from both fragments must survive (union), not last-wins.
+        let updates = UpdateInput::from_fragments(vec![frag_a, frag_b]).unwrap();
+        assert_eq!(updates.exclusions.len(), 2);
+        assert!(matches!(
+            updates.exclusions[0],
+            ExclusionWindowInput::Weekly(_)
+        ));
+        assert!(matches!(
+            updates.exclusions[1],
+            ExclusionWindowInput::Recurring(_)
+        ));
+    }
+
+    #[test]
+    fn test_zone_name_from_zoneinfo_path() {
+        let name = zone_name_from_zoneinfo_path(std::path::Path::new(
+            "/usr/share/zoneinfo/Europe/Berlin",
+        ))
+        .unwrap();
+        assert_eq!(name, "Europe/Berlin");
+
+        let name = zone_name_from_zoneinfo_path(std::path::Path::new(
+            "/usr/share/zoneinfo/UTC",
+        ))
+        .unwrap();
+        assert_eq!(name, "UTC");
+
+        assert!(zone_name_from_zoneinfo_path(std::path::Path::new("/etc/localtime")).is_none());
+    }
+}
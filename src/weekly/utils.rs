@@ -1,23 +1,63 @@
 //! Utilities for weekly-time related logic.
 
 use crate::weekly::{MinuteInWeek, MAX_WEEKLY_MINS, MAX_WEEKLY_SECS};
-use chrono::{DateTime, Utc, Weekday};
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, LocalResult, NaiveDate, NaiveDateTime,
+    NaiveTime, TimeZone, Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
 use failure::{bail, ensure, format_err, Fallible};
 use std::time::Duration;
 
-/// Convert datetime to minutes since beginning of week.
-pub(crate) fn datetime_as_weekly_minute(datetime: &DateTime<Utc>) -> MinuteInWeek {
-    use chrono::{Datelike, Timelike};
+/// Convert a datetime to minutes since the beginning of the week, in the
+/// wall-clock time of `tz` (so a "Sunday 02:00" window stays at 02:00 local
+/// time across DST transitions, rather than drifting in UTC).
+pub(crate) fn datetime_as_weekly_minute(datetime: &DateTime<Utc>, tz: &Tz) -> MinuteInWeek {
+    let local = datetime.with_timezone(tz);
 
-    let weekday = datetime.weekday();
+    let weekday = local.weekday();
     // SAFETY: hour() always <= 23.
-    let hour = datetime.hour() as u8;
+    let hour = local.hour() as u8;
     // SAFETY: minutes() always <= 59.
-    let minute = datetime.minute() as u8;
+    let minute = local.minute() as u8;
 
     time_as_weekly_minute(weekday, hour, minute)
 }
 
+/// Resolve a local wall-clock date/time in `tz` to a concrete instant,
+/// picking the first valid instant past a spring-forward gap, or the
+/// earlier instant of a fall-back overlap.
+pub(crate) fn resolve_local_instant(
+    tz: &Tz,
+    date: NaiveDate,
+    hour: u8,
+    minute: u8,
+) -> Fallible<DateTime<Utc>> {
+    let naive = NaiveDateTime::new(
+        date,
+        NaiveTime::from_hms_opt(u32::from(hour), u32::from(minute), 0)
+            .ok_or_else(|| format_err!("invalid time of day: {}:{}", hour, minute))?,
+    );
+
+    let resolved = match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt,
+        LocalResult::Ambiguous(earlier, _later) => earlier,
+        LocalResult::None => {
+            // Walk forward in one-minute steps until wall-clock time exists
+            // again past the spring-forward gap.
+            let mut probe = naive;
+            loop {
+                probe += ChronoDuration::minutes(1);
+                if let LocalResult::Single(dt) = tz.from_local_datetime(&probe) {
+                    break dt;
+                }
+            }
+        }
+    };
+
+    Ok(resolved.with_timezone(&Utc))
+}
+
 /// Convert a point in weekly-time to minutes since beginning of week.
 pub(crate) fn time_as_weekly_minute(day: chrono::Weekday, hour: u8, minute: u8) -> MinuteInWeek {
     let hour_minutes = u32::from(hour.min(23)).saturating_mul(60);
@@ -49,7 +89,7 @@ pub(crate) fn check_duration(length: &Duration) -> Fallible<()> {
 pub(crate) fn weekday_from_string(input: &str) -> Fallible<Weekday> {
     let day = match input.to_lowercase().as_str() {
         "mon" | "monday" => Weekday::Mon,
-        "tue" | "tuesady" => Weekday::Tue,
+        "tue" | "tuesday" => Weekday::Tue,
         "wed" | "wednesday" => Weekday::Wed,
         "thu" | "thursday" => Weekday::Thu,
         "fri" | "friday" => Weekday::Fri,
@@ -61,6 +101,51 @@ pub(crate) fn weekday_from_string(input: &str) -> Fallible<Weekday> {
     Ok(day)
 }
 
+/// Expand a list of configured window days into concrete week days.
+///
+/// Besides individual day names (as accepted by [`weekday_from_string`]),
+/// the preset keywords `daily` (all seven days), `weekdays` (Mon-Fri) and
+/// `weekends` (Sat-Sun) are accepted. Expansions are deduplicated against
+/// each other and against any explicit day names, and an unrecognized
+/// keyword is a hard error rather than a silently dropped window.
+pub(crate) fn expand_day_keywords(days: &[String]) -> Fallible<Vec<Weekday>> {
+    use std::collections::HashSet;
+
+    let mut seen = HashSet::new();
+    let mut expanded = Vec::new();
+
+    for day in days {
+        let matched = match day.to_lowercase().as_str() {
+            "daily" => vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ],
+            "weekdays" => vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            "weekends" => vec![Weekday::Sat, Weekday::Sun],
+            _ => vec![weekday_from_string(day)?],
+        };
+
+        for weekday in matched {
+            if seen.insert(weekday) {
+                expanded.push(weekday);
+            }
+        }
+    }
+
+    Ok(expanded)
+}
+
 /// Parse a time string (in 24h format).
 ///
 /// ## Example
@@ -101,6 +186,415 @@ pub(crate) fn check_minutes(minutes: u32) -> Fallible<Duration> {
     Ok(length)
 }
 
+/// Frequency component of an RFC 5545 `RRULE`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A `BYDAY` entry: a week day with an optional signed ordinal (e.g. `2TU`
+/// for "second Tuesday", `-1FR` for "last Friday").
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct NthWeekday {
+    pub(crate) ordinal: Option<i32>,
+    pub(crate) weekday: Weekday,
+}
+
+/// A parsed RFC 5545 recurrence rule, for "periodic" update windows that
+/// don't fit a simple weekly grid (e.g. "second Tuesday of each month").
+///
+/// Only the subset of RRULE needed for maintenance-window scheduling is
+/// supported: `FREQ`, `INTERVAL`, `BYDAY`, `BYMONTHDAY`, `BYHOUR` and
+/// `BYMINUTE`, plus a leading `DTSTART` line. `YEARLY` rules recur in the
+/// month of `DTSTART` (`BYMONTH` is not supported), and for `MONTHLY` and
+/// `YEARLY` rules `BYDAY` entries without an ordinal are ignored.
+#[derive(Clone, Debug)]
+pub(crate) struct RecurrenceRule {
+    pub(crate) dtstart: DateTime<Utc>,
+    pub(crate) freq: Frequency,
+    pub(crate) interval: u32,
+    pub(crate) byday: Vec<NthWeekday>,
+    pub(crate) bymonthday: Vec<i32>,
+    pub(crate) byhour: Vec<u8>,
+    pub(crate) byminute: Vec<u8>,
+}
+
+/// How far back (in `INTERVAL`-spaced periods of `freq`) to scan for an
+/// occurrence that may still be open, bounding the walk in `occurrences_near`.
+const MAX_LOOKBACK_PERIODS: i64 = 4;
+
+impl RecurrenceRule {
+    /// Parse an iCalendar-style recurrence, e.g.:
+    ///
+    /// ```text
+    /// DTSTART:20230103T020000Z
+    /// RRULE:FREQ=MONTHLY;BYDAY=2TU;BYHOUR=2;BYMINUTE=0
+    /// ```
+    pub(crate) fn parse(input: &str) -> Fallible<Self> {
+        let mut dtstart = None;
+        let mut rrule_line = None;
+
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if let Some(value) = line.strip_prefix("DTSTART:") {
+                dtstart = Some(parse_ical_datetime(value)?);
+            } else if let Some(value) = line.strip_prefix("RRULE:") {
+                rrule_line = Some(value);
+            } else {
+                bail!("unrecognized recurrence rule line: {}", line);
+            }
+        }
+
+        let dtstart = dtstart.ok_or_else(|| format_err!("recurrence rule is missing DTSTART"))?;
+        let rrule_line =
+            rrule_line.ok_or_else(|| format_err!("recurrence rule is missing RRULE"))?;
+
+        let mut freq = None;
+        let mut interval = 1u32;
+        let mut byday = Vec::new();
+        let mut bymonthday = Vec::new();
+        let mut byhour = Vec::new();
+        let mut byminute = Vec::new();
+
+        for part in rrule_line.split(';') {
+            let mut kv = part.splitn(2, '=');
+            let key = kv.next().unwrap_or_default();
+            let value =
+                kv.next()
+                    .ok_or_else(|| format_err!("malformed RRULE component: {}", part))?;
+
+            match key {
+                "FREQ" => {
+                    freq = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => bail!("unsupported FREQ: {}", other),
+                    });
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse()
+                        .map_err(|_| format_err!("invalid INTERVAL: {}", value))?;
+                    ensure!(interval > 0, "INTERVAL must be greater than zero");
+                }
+                "BYDAY" => {
+                    for entry in value.split(',') {
+                        byday.push(parse_nth_weekday(entry)?);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for entry in value.split(',') {
+                        bymonthday.push(
+                            entry
+                                .parse()
+                                .map_err(|_| format_err!("invalid BYMONTHDAY: {}", entry))?,
+                        );
+                    }
+                }
+                "BYHOUR" => {
+                    for entry in value.split(',') {
+                        byhour.push(
+                            entry
+                                .parse()
+                                .map_err(|_| format_err!("invalid BYHOUR: {}", entry))?,
+                        );
+                    }
+                }
+                "BYMINUTE" => {
+                    for entry in value.split(',') {
+                        byminute.push(
+                            entry
+                                .parse()
+                                .map_err(|_| format_err!("invalid BYMINUTE: {}", entry))?,
+                        );
+                    }
+                }
+                other => bail!("unsupported RRULE component: {}", other),
+            }
+        }
+
+        Ok(Self {
+            dtstart,
+            freq: freq.ok_or_else(|| format_err!("RRULE is missing FREQ"))?,
+            interval,
+            byday,
+            bymonthday,
+            byhour,
+            byminute,
+        })
+    }
+
+    /// Check whether `now` falls inside an occurrence window of `length`,
+    /// evaluated in `tz` (so `BYHOUR`/`BYMINUTE` mean wall-clock time in
+    /// that zone, not UTC).
+    pub(crate) fn is_active(&self, now: &DateTime<Utc>, length: &Duration, tz: &Tz) -> Fallible<bool> {
+        check_duration(length)?;
+        let length = ChronoDuration::from_std(*length)
+            .map_err(|e| format_err!("invalid window length: {}", e))?;
+
+        Ok(self
+            .occurrences_near(now, tz)?
+            .into_iter()
+            .any(|start| start <= *now && *now < start + length))
+    }
+
+    /// Enumerate candidate occurrence starts for the last
+    /// `MAX_LOOKBACK_PERIODS * INTERVAL` periods up to and including `now`,
+    /// respecting `INTERVAL` counted from `DTSTART`. The scan must cover
+    /// `INTERVAL` raw periods per selected occurrence, or a large
+    /// `INTERVAL` (e.g. `FREQ=DAILY;INTERVAL=10`) would never walk back far
+    /// enough to find the still-open previous occurrence.
+    fn occurrences_near(&self, now: &DateTime<Utc>, tz: &Tz) -> Fallible<Vec<DateTime<Utc>>> {
+        let dtstart_local = self.dtstart.with_timezone(tz).naive_local();
+        let now_local = now.with_timezone(tz).naive_local();
+
+        let mut occurrences = Vec::new();
+        let lookback = MAX_LOOKBACK_PERIODS * i64::from(self.interval);
+
+        for back in 0..lookback {
+            let anchor = match self.freq {
+                Frequency::Daily => now_local - ChronoDuration::days(back),
+                Frequency::Weekly => now_local - ChronoDuration::weeks(back),
+                Frequency::Monthly => shift_months(now_local, -back),
+                Frequency::Yearly => shift_months(now_local, -back * 12),
+            };
+
+            if Self::period_matches_interval(self.freq, self.interval, dtstart_local, anchor) {
+                occurrences.extend(self.candidates_in_period(anchor, dtstart_local, tz)?);
+            }
+        }
+
+        Ok(occurrences)
+    }
+
+    /// Whether the period containing `anchor` is one that `interval`
+    /// (counted from `dtstart`) selects.
+    fn period_matches_interval(
+        freq: Frequency,
+        interval: u32,
+        dtstart: NaiveDateTime,
+        anchor: NaiveDateTime,
+    ) -> bool {
+        let periods_since_start = match freq {
+            Frequency::Daily => (anchor.date() - dtstart.date()).num_days(),
+            Frequency::Weekly => {
+                (week_start(anchor.date()) - week_start(dtstart.date())).num_weeks()
+            }
+            Frequency::Monthly => months_between(dtstart, anchor),
+            Frequency::Yearly => i64::from(anchor.year() - dtstart.year()),
+        };
+
+        periods_since_start >= 0 && periods_since_start % i64::from(interval) == 0
+    }
+
+    /// Resolve the concrete occurrence instants inside the local period
+    /// (day, week or month, depending on `freq`; for `YEARLY`, the month of
+    /// `DTSTART` in the year) that contains `anchor`, converting each local wall-clock candidate to a UTC
+    /// instant via `tz` (DST-correct).
+    fn candidates_in_period(
+        &self,
+        anchor: NaiveDateTime,
+        dtstart_local: NaiveDateTime,
+        tz: &Tz,
+    ) -> Fallible<Vec<DateTime<Utc>>> {
+        let dates: Vec<NaiveDate> = match self.freq {
+            Frequency::Daily => vec![anchor.date()],
+            Frequency::Weekly => {
+                let monday = week_start(anchor.date());
+                if self.byday.is_empty() {
+                    vec![monday + ChronoDuration::days(i64::from(
+                        dtstart_local.weekday().num_days_from_monday(),
+                    ))]
+                } else {
+                    self.byday
+                        .iter()
+                        .map(|d| {
+                            monday + ChronoDuration::days(i64::from(d.weekday.num_days_from_monday()))
+                        })
+                        .collect()
+                }
+            }
+            Frequency::Monthly | Frequency::Yearly => {
+                let month = if self.freq == Frequency::Yearly {
+                    dtstart_local.month()
+                } else {
+                    anchor.month()
+                };
+                let mut dates = Vec::new();
+
+                for d in &self.byday {
+                    if let Some(ordinal) = d.ordinal {
+                        if let Some(date) =
+                            nth_weekday_of_month(anchor.year(), month, d.weekday, ordinal)
+                        {
+                            dates.push(date);
+                        }
+                    }
+                }
+
+                if !self.bymonthday.is_empty() {
+                    let days_in_month = days_in_month(anchor.year(), month) as i32;
+                    for &day in &self.bymonthday {
+                        let day = if day < 0 { days_in_month + day + 1 } else { day };
+                        if let Some(date) =
+                            NaiveDate::from_ymd_opt(anchor.year(), month, day as u32)
+                        {
+                            dates.push(date);
+                        }
+                    }
+                }
+
+                if self.byday.is_empty() && self.bymonthday.is_empty() {
+                    if let Some(date) = NaiveDate::from_ymd_opt(
+                        anchor.year(),
+                        month,
+                        dtstart_local.day(),
+                    ) {
+                        dates.push(date);
+                    }
+                }
+
+                dates
+            }
+        };
+
+        let hours = if self.byhour.is_empty() {
+            vec![dtstart_local.hour() as u8]
+        } else {
+            self.byhour.clone()
+        };
+        let minutes = if self.byminute.is_empty() {
+            vec![dtstart_local.minute() as u8]
+        } else {
+            self.byminute.clone()
+        };
+
+        let mut occurrences = Vec::new();
+        for date in dates {
+            for &hour in &hours {
+                for &minute in &minutes {
+                    occurrences.push(resolve_local_instant(tz, date, hour, minute)?);
+                }
+            }
+        }
+
+        Ok(occurrences)
+    }
+}
+
+/// Parse a basic-format iCalendar UTC date-time, e.g. `20230103T020000Z`.
+fn parse_ical_datetime(input: &str) -> Fallible<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(input, "%Y%m%dT%H%M%SZ")
+        .map_err(|_| format_err!("invalid DTSTART value: {}", input))?;
+    Ok(Utc.from_utc_datetime(&naive))
+}
+
+/// Parse a `BYDAY` entry, e.g. `2TU`, `-1FR`, or a bare `MO`.
+fn parse_nth_weekday(input: &str) -> Fallible<NthWeekday> {
+    let split_at = input
+        .find(|c: char| c.is_ascii_alphabetic())
+        .ok_or_else(|| format_err!("invalid BYDAY entry: {}", input))?;
+    let (ordinal, day) = input.split_at(split_at);
+
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        Some(
+            ordinal
+                .parse::<i32>()
+                .map_err(|_| format_err!("invalid BYDAY ordinal: {}", input))?,
+        )
+    };
+
+    let weekday = match day {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        other => bail!("unrecognized BYDAY week day: {}", other),
+    };
+
+    Ok(NthWeekday { ordinal, weekday })
+}
+
+/// The Monday that starts the week containing `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - ChronoDuration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+/// Number of days in the given year/month.
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .expect("valid next month")
+        .pred_opt()
+        .expect("valid previous day")
+        .day()
+}
+
+/// The `ordinal`-th occurrence of `weekday` in `year`/`month` (1-based;
+/// negative counts back from the end of the month). Returns `None` if the
+/// month doesn't have that many occurrences of `weekday`.
+fn nth_weekday_of_month(
+    year: i32,
+    month: u32,
+    weekday: Weekday,
+    ordinal: i32,
+) -> Option<NaiveDate> {
+    if ordinal == 0 {
+        return None;
+    }
+
+    if ordinal > 0 {
+        let first_of_month = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let offset = (7 + weekday.num_days_from_monday() as i64
+            - first_of_month.weekday().num_days_from_monday() as i64)
+            % 7;
+        let first_match = first_of_month + ChronoDuration::days(offset);
+        let candidate = first_match + ChronoDuration::days(i64::from(ordinal - 1) * 7);
+        (candidate.month() == month).then_some(candidate)
+    } else {
+        let last_of_month = NaiveDate::from_ymd_opt(year, month, days_in_month(year, month))?;
+        let offset = (7 + last_of_month.weekday().num_days_from_monday() as i64
+            - weekday.num_days_from_monday() as i64)
+            % 7;
+        let last_match = last_of_month - ChronoDuration::days(offset);
+        let candidate = last_match - ChronoDuration::days(i64::from(-ordinal - 1) * 7);
+        (candidate.month() == month).then_some(candidate)
+    }
+}
+
+/// Number of whole months between two instants (`to` minus `from`), which
+/// may be negative.
+fn months_between(from: NaiveDateTime, to: NaiveDateTime) -> i64 {
+    i64::from(to.year() - from.year()) * 12 + i64::from(to.month() as i32 - from.month() as i32)
+}
+
+/// Shift `datetime` by a (possibly negative) number of months, clamping the
+/// day of month into the target month.
+fn shift_months(datetime: NaiveDateTime, months: i64) -> NaiveDateTime {
+    let total_months = i64::from(datetime.year()) * 12 + i64::from(datetime.month() as i64 - 1) + months;
+    let year = (total_months.div_euclid(12)) as i32;
+    let month = (total_months.rem_euclid(12)) as u32 + 1;
+    let day = datetime.day().min(days_in_month(year, month));
+
+    NaiveDateTime::new(
+        NaiveDate::from_ymd_opt(year, month, day).expect("valid shifted date"),
+        datetime.time(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +642,45 @@ mod tests {
         weekday_from_string("domenica").unwrap_err();
     }
 
+    #[test]
+    fn test_expand_day_keywords() {
+        let daily = expand_day_keywords(&["daily".to_string()]).unwrap();
+        assert_eq!(
+            daily,
+            vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ]
+        );
+
+        let weekdays = expand_day_keywords(&["weekdays".to_string()]).unwrap();
+        assert_eq!(
+            weekdays,
+            vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ]
+        );
+
+        let weekends = expand_day_keywords(&["weekends".to_string()]).unwrap();
+        assert_eq!(weekends, vec![Weekday::Sat, Weekday::Sun]);
+
+        // Overlap between a keyword and an explicit day is deduplicated.
+        let overlap =
+            expand_day_keywords(&["weekdays".to_string(), "monday".to_string()]).unwrap();
+        assert_eq!(overlap.len(), 5);
+
+        expand_day_keywords(&["fortnightly".to_string()]).unwrap_err();
+    }
+
     #[test]
     fn test_time_from_string() {
         let t1 = time_from_string("12:45").unwrap();
@@ -192,4 +725,124 @@ mod tests {
             prop_assert!(res < MAX_WEEKLY_MINS);
         }
     }
+
+    #[test]
+    fn test_parse_rrule_monthly_byday() {
+        let rule = RecurrenceRule::parse(
+            "DTSTART:20230103T020000Z\nRRULE:FREQ=MONTHLY;BYDAY=2TU;BYHOUR=2;BYMINUTE=0",
+        )
+        .unwrap();
+
+        assert_eq!(rule.freq, Frequency::Monthly);
+        assert_eq!(rule.interval, 1);
+        assert_eq!(
+            rule.byday,
+            vec![NthWeekday {
+                ordinal: Some(2),
+                weekday: Weekday::Tue,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_rrule_missing_parts() {
+        RecurrenceRule::parse("RRULE:FREQ=MONTHLY;BYDAY=2TU").unwrap_err();
+        RecurrenceRule::parse("DTSTART:20230103T020000Z").unwrap_err();
+        RecurrenceRule::parse("DTSTART:20230103T020000Z\nRRULE:BYDAY=2TU").unwrap_err();
+        RecurrenceRule::parse("DTSTART:20230103T020000Z\nRRULE:FREQ=FORTNIGHTLY").unwrap_err();
+    }
+
+    #[test]
+    fn test_nth_weekday_of_month_patch_tuesday() {
+        // Patch Tuesday, January 2023: the second Tuesday is the 10th.
+        let date = nth_weekday_of_month(2023, 1, Weekday::Tue, 2).unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2023, 1, 10).unwrap());
+    }
+
+    #[test]
+    fn test_nth_weekday_of_month_last_friday() {
+        // Last Friday of January 2023 is the 27th.
+        let date = nth_weekday_of_month(2023, 1, Weekday::Fri, -1).unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2023, 1, 27).unwrap());
+    }
+
+    #[test]
+    fn test_rrule_is_active_monthly() {
+        let rule = RecurrenceRule::parse(
+            "DTSTART:20230103T020000Z\nRRULE:FREQ=MONTHLY;BYDAY=2TU;BYHOUR=2;BYMINUTE=0",
+        )
+        .unwrap();
+        let length = Duration::from_secs(60 * 60);
+
+        // Second Tuesday of February 2023 is the 14th.
+        let inside = Utc.with_ymd_and_hms(2023, 2, 14, 2, 30, 0).unwrap();
+        assert!(rule.is_active(&inside, &length, &chrono_tz::UTC).unwrap());
+
+        let before = Utc.with_ymd_and_hms(2023, 2, 14, 1, 59, 0).unwrap();
+        assert!(!rule.is_active(&before, &length, &chrono_tz::UTC).unwrap());
+
+        let after = Utc.with_ymd_and_hms(2023, 2, 14, 3, 0, 0).unwrap();
+        assert!(!rule.is_active(&after, &length, &chrono_tz::UTC).unwrap());
+    }
+
+    #[test]
+    fn test_rrule_is_active_yearly_last_friday() {
+        let rule = RecurrenceRule::parse(
+            "DTSTART:20230331T000000Z\nRRULE:FREQ=YEARLY;BYDAY=-1FR;BYHOUR=0;BYMINUTE=0",
+        )
+        .unwrap();
+        let length = Duration::from_secs(60 * 60);
+
+        // Last Friday of March 2024 is the 29th.
+        let inside = Utc.with_ymd_and_hms(2024, 3, 29, 0, 15, 0).unwrap();
+        assert!(rule.is_active(&inside, &length, &chrono_tz::UTC).unwrap());
+
+        let outside = Utc.with_ymd_and_hms(2024, 3, 28, 0, 15, 0).unwrap();
+        assert!(!rule.is_active(&outside, &length, &chrono_tz::UTC).unwrap());
+    }
+
+    #[test]
+    fn test_rrule_is_active_large_interval_still_open() {
+        // A 7-day window on a 10-day cadence: the Jan-1 occurrence is still
+        // open on Jan-7, well past a 4-period (raw day) lookback, which
+        // would give up before ever reconstructing the Jan-1 candidate.
+        let rule =
+            RecurrenceRule::parse("DTSTART:20230101T000000Z\nRRULE:FREQ=DAILY;INTERVAL=10")
+                .unwrap();
+        let length = Duration::from_secs(7 * 24 * 60 * 60);
+
+        let now = Utc.with_ymd_and_hms(2023, 1, 7, 12, 0, 0).unwrap();
+        assert!(rule.is_active(&now, &length, &chrono_tz::UTC).unwrap());
+
+        let after_window = Utc.with_ymd_and_hms(2023, 1, 8, 12, 0, 0).unwrap();
+        assert!(!rule.is_active(&after_window, &length, &chrono_tz::UTC).unwrap());
+    }
+
+    #[test]
+    fn test_datetime_as_weekly_minute_local_tz() {
+        let tz = chrono_tz::America::New_York;
+        // 2023-06-04 is a Sunday; 06:00 UTC is 02:00 EDT (-04:00).
+        let datetime = Utc.with_ymd_and_hms(2023, 6, 4, 6, 0, 0).unwrap();
+        let expected = time_as_weekly_minute(Weekday::Sun, 2, 0);
+        assert_eq!(datetime_as_weekly_minute(&datetime, &tz), expected);
+    }
+
+    #[test]
+    fn test_resolve_local_instant_spring_forward_gap() {
+        let tz = chrono_tz::America::New_York;
+        // On 2023-03-12, clocks jump from 02:00 to 03:00: 02:30 never occurs.
+        let date = NaiveDate::from_ymd_opt(2023, 3, 12).unwrap();
+        let resolved = resolve_local_instant(&tz, date, 2, 30).unwrap();
+        assert_eq!(resolved, Utc.with_ymd_and_hms(2023, 3, 12, 7, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_resolve_local_instant_fall_back_ambiguous() {
+        let tz = chrono_tz::America::New_York;
+        // On 2023-11-05, clocks fall back from 02:00 to 01:00: 01:30 occurs
+        // twice. The earlier (EDT, -04:00) instant should be picked.
+        let date = NaiveDate::from_ymd_opt(2023, 11, 5).unwrap();
+        let resolved = resolve_local_instant(&tz, date, 1, 30).unwrap();
+        assert_eq!(resolved, Utc.with_ymd_and_hms(2023, 11, 5, 5, 30, 0).unwrap());
+    }
 }